@@ -3,7 +3,7 @@
 //!
 //! ## Examples
 //!
-//! Encoding:
+//! Encoding and decoding:
 //!
 //! ```rust
 //! fn main() {
@@ -12,7 +12,9 @@
 //!
 //!     println!("normal: {}, compressed: {}", data.len(), compressed.len());
 //!
-//!     // will show "normal: 16, compressed: 10"
+//!     // will show "normal: 16, compressed: 7"
+//!
+//!     assert_eq!(rle::decompress(compressed).unwrap(), data);
 //! }
 //! ```
 //!
@@ -32,29 +34,163 @@
 //! hellllllllllllllllllllllllllllllllllllllllllllo!
 //! ```
 //!
-//! This is due to the encoding using a `u32` under the hood to store the length,
-//! which means the it can store up to ~4 billion repeating characters until
-//! overflow. A run-length-encoded block would look like the following for the
-//! previous example:
+//! The length of a run is stored as a [CompactSize](https://developer.bitcoin.org/reference/transactions.html#compactsize-unsigned-integers)-style
+//! varint rather than a fixed-width integer, so short runs don't pay for bytes
+//! they don't need while a run can still grow as large as a `u64` allows. A
+//! run-length-encoded block would look like the following for the previous
+//! example:
 //!
 //! ```none
-//! [h, e, 4, 0, 0, 0, 44, o, !]
+//! [h, e, 4, 44, 108, o, !]
 //! ```
 //!
 //! You may assume whatever binary encoding you'd like for these letters to
 //! properly expand this block, but in essense it uses an [End-of-Transmission character](https://en.wikipedia.org/wiki/End-of-Transmission_character)
-//! to represent the start of an run-length-encoded block and has a `[u8; 4]`
-//! (which represents the previously mentioned `u32` in big-endian form).
+//! to represent the start of a run-length-encoded block, followed by the varint
+//! length (here a single byte, `44`) and then the repeated byte (`108`, ASCII `l`).
 //!
 //! After that, it simply has a `u8` for the byte it is representing and continued
 //! further onwards; looping this compression/decompression until the end of the
 //! inputted bytes.
+//!
+//! ## The `END_OF_TRANSMISSION` byte
+//!
+//! Because a real run-length block is always at least as long as the run
+//! threshold (6x by default, and never below 1x), its length can never be `0`. This leaves a length of `0` free to mean something else: a
+//! literal [`END_OF_TRANSMISSION`] byte appearing in the original data. Whenever
+//! this byte occurs outside of a run, it's escaped as `[4, 0]` (an
+//! `END_OF_TRANSMISSION` followed by a zero-length varint) rather than being let
+//! through bare, which keeps the format lossless for arbitrary binary input.
+//!
+//! ## An alternate codec: PackBits
+//!
+//! [`compress_packbits`]/[`decompress_packbits`] implement the classic
+//! [PackBits](https://en.wikipedia.org/wiki/PackBits) scheme instead, as used
+//! by MacPaint and TIFF. Where the default codec above only ever collapses
+//! repeated bytes and leaves everything else byte-for-byte, PackBits also
+//! frames non-repeating stretches into bounded "literal" blocks. This caps
+//! the worst-case expansion at a predictable ratio, at the cost of slightly
+//! worse compression on long runs than the varint-based scheme.
+
+use std::io::Read as _;
 
 /// The [End-of-Transmission character](https://en.wikipedia.org/wiki/End-of-Transmission_character),
 /// which in ASCII and Unicode is the 4th character
 const END_OF_TRANSMISSION: u8 = 4;
 
-/// Compresses to custom `rle` from given bytes
+/// Writes `n` as a [CompactSize](https://developer.bitcoin.org/reference/transactions.html#compactsize-unsigned-integers)-style
+/// varint, used to store a run length without wasting space on short runs
+fn write_varint(n: u64, output: &mut Vec<u8>) {
+    if n <= 0xFC {
+        output.push(n as u8);
+    } else if n <= 0xFFFF {
+        output.push(0xFD);
+        output.extend_from_slice(&(n as u16).to_le_bytes());
+    } else if n <= 0xFFFFFFFF {
+        output.push(0xFE);
+        output.extend_from_slice(&(n as u32).to_le_bytes());
+    } else {
+        output.push(0xFF);
+        output.extend_from_slice(&n.to_le_bytes());
+    }
+}
+
+/// Reads a varint written by [`write_varint`] starting at `*i`, advancing `*i`
+/// past it
+fn read_varint(data: &[u8], i: &mut usize) -> Result<u64, DecompressError> {
+    let prefix = *data.get(*i).ok_or(DecompressError::UnexpectedEof)?;
+    *i += 1;
+
+    match prefix {
+        0xFD => {
+            let bytes = data.get(*i..*i + 2).ok_or(DecompressError::UnexpectedEof)?;
+            *i += 2;
+            Ok(u16::from_le_bytes([bytes[0], bytes[1]]) as u64)
+        }
+        0xFE => {
+            let bytes = data.get(*i..*i + 4).ok_or(DecompressError::UnexpectedEof)?;
+            *i += 4;
+            Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as u64)
+        }
+        0xFF => {
+            let bytes = data.get(*i..*i + 8).ok_or(DecompressError::UnexpectedEof)?;
+            *i += 8;
+            Ok(u64::from_le_bytes(bytes.try_into().unwrap()))
+        }
+        n => Ok(n as u64),
+    }
+}
+
+/// A coarser-grained alternative to tuning [`Config::threshold`] directly
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionLevel {
+    /// Collapses runs as short as 4 bytes, the shortest run that comes out
+    /// smaller than the cheapest possible RLE block (`END_OF_TRANSMISSION` + a 1-byte
+    /// varint length + the value byte, 3 bytes total)
+    Aggressive,
+    /// The default threshold of 6 bytes, matching this crate's behavior from
+    /// before the threshold became configurable
+    Balanced,
+    /// Only collapses runs of 16 bytes or more, minimizing worst-case expansion
+    Conservative,
+}
+
+/// The default run threshold, also used by [`RleWriter`] which doesn't
+/// support a configurable threshold
+const DEFAULT_THRESHOLD: u64 = 6;
+
+impl CompressionLevel {
+    fn threshold(self) -> u64 {
+        match self {
+            Self::Aggressive => 4,
+            Self::Balanced => DEFAULT_THRESHOLD,
+            Self::Conservative => 16,
+        }
+    }
+}
+
+/// Configures how [`compress_with`] decides when a run is worth collapsing
+/// into an RLE block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    threshold: u64,
+}
+
+impl Config {
+    /// Starts a [`Config`] with the default threshold (see [`CompressionLevel::Balanced`])
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the minimum run length, in bytes, at which a run is collapsed into
+    /// an RLE block rather than passed through literally
+    ///
+    /// A real run-length block can never represent a run of `0` bytes (that
+    /// value is reserved to escape a literal [`END_OF_TRANSMISSION`] byte), so
+    /// `threshold` is clamped to a minimum of `1`.
+    pub fn threshold(mut self, threshold: u64) -> Self {
+        self.threshold = threshold.max(1);
+        self
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            threshold: CompressionLevel::Balanced.threshold(),
+        }
+    }
+}
+
+impl From<CompressionLevel> for Config {
+    fn from(level: CompressionLevel) -> Self {
+        Self {
+            threshold: level.threshold(),
+        }
+    }
+}
+
+/// Compresses to custom `rle` from given bytes, using the default [`Config`]
 ///
 /// # Example
 ///
@@ -65,16 +201,40 @@ const END_OF_TRANSMISSION: u8 = 4;
 ///
 ///     println!("normal: {}, compressed: {}", data.len(), compressed.len());
 ///
-///     // will show "normal: 16, compressed: 10"
+///     // will show "normal: 16, compressed: 7"
 /// }
 /// ```
 pub fn compress(data: impl AsRef<[u8]>) -> Vec<u8> {
-    fn compute_buf(buf: &mut (u8, u32), output: &mut Vec<u8>) {
-        if buf.1 >= 6 {
+    compress_with(data, Config::default())
+}
+
+/// Compresses to custom `rle` from given bytes, using a custom [`Config`]
+///
+/// # Example
+///
+/// ```rust
+/// fn main() {
+///     let data = &[0, 0, 0, 0, 1];
+///     let config = rle::Config::from(rle::CompressionLevel::Aggressive);
+///
+///     assert_eq!(rle::compress_with(data, config).len(), 4);
+/// }
+/// ```
+pub fn compress_with(data: impl AsRef<[u8]>, config: Config) -> Vec<u8> {
+    fn compute_buf(buf: &mut (u8, u64), output: &mut Vec<u8>, threshold: u64) {
+        if buf.1 >= threshold {
             // do RLE if more efficiant to do so
             output.push(END_OF_TRANSMISSION);
-            output.extend_from_slice(&buf.1.to_be_bytes());
+            write_varint(buf.1, output);
             output.push(buf.0)
+        } else if buf.0 == END_OF_TRANSMISSION {
+            // a literal END_OF_TRANSMISSION can't be let through bare, as it would
+            // be mistaken for the start of a run-length block, so escape it using
+            // a zero length, which a real run can never have (it's always >= threshold)
+            for _ in 0..buf.1 {
+                output.push(END_OF_TRANSMISSION);
+                write_varint(0, output);
+            }
         } else {
             // add normal manual
             for _ in 0..buf.1 {
@@ -84,23 +244,439 @@ pub fn compress(data: impl AsRef<[u8]>) -> Vec<u8> {
     }
 
     let mut output = Vec::new();
-    let mut buf: (u8, u32) = (0, 0);
+    let mut buf: (u8, u64) = (0, 0);
 
     for byte in data.as_ref() {
         if *byte == buf.0 {
             buf.1 += 1;
         } else {
-            compute_buf(&mut buf, &mut output);
+            compute_buf(&mut buf, &mut output, config.threshold);
             buf = (*byte, 1);
         }
     }
 
-    compute_buf(&mut buf, &mut output);
+    compute_buf(&mut buf, &mut output, config.threshold);
 
     output
 }
 
-// TODO: decoding
+/// An error which may occur while [`decompress`]ing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecompressError {
+    /// An [`END_OF_TRANSMISSION`] marker was found but there weren't enough
+    /// bytes left to read the varint run length and the following data byte
+    UnexpectedEof,
+}
+
+impl std::fmt::Display for DecompressError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnexpectedEof => write!(f, "truncated run-length block at end of input"),
+        }
+    }
+}
+
+impl std::error::Error for DecompressError {}
+
+/// Decompresses custom `rle` back into the original bytes, inverting [`compress`]
+///
+/// # Example
+///
+/// ```rust
+/// fn main() {
+///     let data = &[44, 43, 6, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 3];
+///     let compressed = rle::compress(data);
+///     let decompressed = rle::decompress(compressed).unwrap();
+///
+///     assert_eq!(decompressed, data);
+/// }
+/// ```
+pub fn decompress(data: impl AsRef<[u8]>) -> Result<Vec<u8>, DecompressError> {
+    let data = data.as_ref();
+    let mut output = Vec::new();
+    let mut i = 0;
+
+    while i < data.len() {
+        let byte = data[i];
+
+        if byte == END_OF_TRANSMISSION {
+            let mut j = i + 1;
+            let len = read_varint(data, &mut j)?;
+
+            if len == 0 {
+                // escaped literal END_OF_TRANSMISSION, see `compress`
+                output.push(END_OF_TRANSMISSION);
+                i = j;
+                continue;
+            }
+
+            let value = *data.get(j).ok_or(DecompressError::UnexpectedEof)?;
+
+            for _ in 0..len {
+                output.push(value);
+            }
+
+            i = j + 1;
+        } else {
+            output.push(byte);
+            i += 1;
+        }
+    }
+
+    Ok(output)
+}
+
+/// An error which may occur while [`decompress_packbits`]ing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackBitsError {
+    /// A control byte promised more literal or repeated bytes than were left
+    /// in the input
+    UnexpectedEof,
+}
+
+impl std::fmt::Display for PackBitsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnexpectedEof => write!(f, "truncated packbits block at end of input"),
+        }
+    }
+}
+
+impl std::error::Error for PackBitsError {}
+
+/// Compresses to [PackBits](https://en.wikipedia.org/wiki/PackBits) from given bytes
+///
+/// Unlike [`compress`], this never collapses a run shorter than 2 bytes and
+/// caps every block at 128 bytes, so the worst-case expansion is bounded at
+/// roughly 1/128th of the input rather than being unbounded for non-repetitive
+/// data.
+///
+/// # Example
+///
+/// ```rust
+/// fn main() {
+///     let data = &[1, 2, 2, 2, 3];
+///     let compressed = rle::compress_packbits(data);
+///
+///     assert_eq!(rle::decompress_packbits(compressed).unwrap(), data);
+/// }
+/// ```
+pub fn compress_packbits(data: impl AsRef<[u8]>) -> Vec<u8> {
+    let data = data.as_ref();
+    let mut output = Vec::new();
+    let mut i = 0;
+
+    while i < data.len() {
+        let mut run_len = 1;
+        while run_len < 128 && i + run_len < data.len() && data[i + run_len] == data[i] {
+            run_len += 1;
+        }
+
+        if run_len >= 2 {
+            // repeat block: control byte counts down from -1 to -127
+            output.push((1 - run_len as i32) as i8 as u8);
+            output.push(data[i]);
+            i += run_len;
+        } else {
+            // literal block: gather bytes up to the next run of 3 or more or the
+            // 128 cap; a 2-byte run inside a literal stays literal, as splitting
+            // the block there would cost an extra control byte
+            let start = i;
+            i += 1;
+            while i < data.len()
+                && i - start < 128
+                && !(i + 2 < data.len() && data[i] == data[i + 1] && data[i] == data[i + 2])
+            {
+                i += 1;
+            }
+            output.push((i - start - 1) as u8);
+            output.extend_from_slice(&data[start..i]);
+        }
+    }
+
+    output
+}
+
+/// Decompresses [PackBits](https://en.wikipedia.org/wiki/PackBits) back into
+/// the original bytes, inverting [`compress_packbits`]
+///
+/// # Example
+///
+/// ```rust
+/// fn main() {
+///     let data = &[1, 2, 2, 2, 3];
+///     let compressed = rle::compress_packbits(data);
+///     let decompressed = rle::decompress_packbits(compressed).unwrap();
+///
+///     assert_eq!(decompressed, data);
+/// }
+/// ```
+pub fn decompress_packbits(data: impl AsRef<[u8]>) -> Result<Vec<u8>, PackBitsError> {
+    let data = data.as_ref();
+    let mut output = Vec::new();
+    let mut i = 0;
+
+    while i < data.len() {
+        let control = data[i] as i8;
+        i += 1;
+
+        if control == -128 {
+            // no-op control byte, skip
+        } else if control >= 0 {
+            let len = control as usize + 1;
+            let literal = data.get(i..i + len).ok_or(PackBitsError::UnexpectedEof)?;
+            output.extend_from_slice(literal);
+            i += len;
+        } else {
+            let count = 1 - control as i32;
+            let byte = *data.get(i).ok_or(PackBitsError::UnexpectedEof)?;
+
+            for _ in 0..count {
+                output.push(byte);
+            }
+
+            i += 1;
+        }
+    }
+
+    Ok(output)
+}
+
+/// A [`Write`](std::io::Write) adapter which RLE-compresses bytes written to it and forwards
+/// the compressed blocks to an inner writer, in the spirit of
+/// [`flate2`](https://docs.rs/flate2)'s encoder wrappers
+///
+/// Writes are not compressed byte-by-byte: a run spanning multiple [`write`](std::io::Write::write)
+/// calls is coalesced into a single block, and the pending run is only
+/// flushed once a differing byte arrives or [`finish`](RleWriter::finish) is
+/// called. Dropping an [`RleWriter`] without calling [`finish`](RleWriter::finish)
+/// still flushes the pending run on a best-effort basis, but I/O errors at
+/// that point are silently discarded, so prefer calling [`finish`](RleWriter::finish) explicitly.
+///
+/// Unlike [`compress_with`], this always uses the default run threshold and
+/// has no [`Config`] of its own to tune it.
+///
+/// # Example
+///
+/// ```rust
+/// use std::io::Write;
+///
+/// fn main() {
+///     let mut writer = rle::RleWriter::new(Vec::new());
+///     writer.write_all(&[1, 1, 1, 1, 1, 1, 1, 2]).unwrap();
+///     let compressed = writer.finish().unwrap();
+///
+///     assert_eq!(rle::decompress(compressed).unwrap(), &[1, 1, 1, 1, 1, 1, 1, 2]);
+/// }
+/// ```
+pub struct RleWriter<W: std::io::Write> {
+    inner: Option<W>,
+    run: Option<(u8, u64)>,
+}
+
+impl<W: std::io::Write> RleWriter<W> {
+    /// Wraps `inner`, compressing bytes written through this writer before
+    /// they reach it
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner: Some(inner),
+            run: None,
+        }
+    }
+
+    fn flush_run(&mut self, value: u8, count: u64) -> std::io::Result<()> {
+        let mut block = Vec::new();
+
+        if count >= DEFAULT_THRESHOLD {
+            block.push(END_OF_TRANSMISSION);
+            write_varint(count, &mut block);
+            block.push(value);
+        } else if value == END_OF_TRANSMISSION {
+            for _ in 0..count {
+                block.push(END_OF_TRANSMISSION);
+                write_varint(0, &mut block);
+            }
+        } else {
+            for _ in 0..count {
+                block.push(value);
+            }
+        }
+
+        self.inner
+            .as_mut()
+            .expect("RleWriter used after finish")
+            .write_all(&block)
+    }
+
+    /// Flushes the pending run and returns the inner writer
+    pub fn finish(mut self) -> std::io::Result<W> {
+        if let Some((value, count)) = self.run.take() {
+            self.flush_run(value, count)?;
+        }
+
+        let mut inner = self.inner.take().expect("RleWriter used after finish");
+        inner.flush()?;
+        Ok(inner)
+    }
+}
+
+impl<W: std::io::Write> std::io::Write for RleWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        for &byte in buf {
+            match self.run {
+                Some((value, count)) if value == byte => self.run = Some((value, count + 1)),
+                Some((value, count)) => {
+                    self.flush_run(value, count)?;
+                    self.run = Some((byte, 1));
+                }
+                None => self.run = Some((byte, 1)),
+            }
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner
+            .as_mut()
+            .expect("RleWriter used after finish")
+            .flush()
+    }
+}
+
+impl<W: std::io::Write> Drop for RleWriter<W> {
+    fn drop(&mut self) {
+        if let Some((value, count)) = self.run.take() {
+            let _ = self.flush_run(value, count);
+        }
+    }
+}
+
+/// A [`Read`](std::io::Read) adapter which decompresses RLE-compressed bytes from an inner
+/// reader on the fly, in the spirit of [`flate2`](https://docs.rs/flate2)'s
+/// decoder wrappers
+///
+/// # Example
+///
+/// ```rust
+/// use std::io::Read;
+///
+/// fn main() {
+///     let compressed = rle::compress(&[1, 1, 1, 1, 1, 1, 1, 2]);
+///     let mut reader = rle::RleReader::new(compressed.as_slice());
+///     let mut decompressed = Vec::new();
+///     reader.read_to_end(&mut decompressed).unwrap();
+///
+///     assert_eq!(decompressed, &[1, 1, 1, 1, 1, 1, 1, 2]);
+/// }
+/// ```
+pub struct RleReader<R: std::io::Read> {
+    inner: std::io::BufReader<R>,
+    pending: std::collections::VecDeque<u8>,
+    eof: bool,
+}
+
+impl<R: std::io::Read> RleReader<R> {
+    /// Wraps `inner`, decompressing bytes read through this reader as they
+    /// come off of it
+    ///
+    /// `inner` is internally wrapped in a [`BufReader`](std::io::BufReader),
+    /// so decoding an unbuffered source (a raw socket or file) doesn't cost a
+    /// syscall per decompressed byte.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner: std::io::BufReader::new(inner),
+            pending: std::collections::VecDeque::new(),
+            eof: false,
+        }
+    }
+
+    fn read_byte(&mut self) -> std::io::Result<Option<u8>> {
+        let mut byte = [0u8; 1];
+
+        match self.inner.read(&mut byte)? {
+            0 => Ok(None),
+            _ => Ok(Some(byte[0])),
+        }
+    }
+
+    fn require_byte(&mut self) -> std::io::Result<u8> {
+        self.read_byte()?
+            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "truncated run-length block"))
+    }
+
+    fn read_varint(&mut self) -> std::io::Result<u64> {
+        match self.require_byte()? {
+            0xFD => {
+                let bytes = [self.require_byte()?, self.require_byte()?];
+                Ok(u16::from_le_bytes(bytes) as u64)
+            }
+            0xFE => {
+                let bytes = [
+                    self.require_byte()?,
+                    self.require_byte()?,
+                    self.require_byte()?,
+                    self.require_byte()?,
+                ];
+                Ok(u32::from_le_bytes(bytes) as u64)
+            }
+            0xFF => {
+                let mut bytes = [0u8; 8];
+                for b in &mut bytes {
+                    *b = self.require_byte()?;
+                }
+                Ok(u64::from_le_bytes(bytes))
+            }
+            n => Ok(n as u64),
+        }
+    }
+
+    /// Pulls bytes from the inner reader until at least one decompressed
+    /// byte is ready, or the inner reader is exhausted
+    fn fill(&mut self) -> std::io::Result<()> {
+        while self.pending.is_empty() && !self.eof {
+            let byte = match self.read_byte()? {
+                Some(byte) => byte,
+                None => {
+                    self.eof = true;
+                    break;
+                }
+            };
+
+            if byte == END_OF_TRANSMISSION {
+                let len = self.read_varint()?;
+
+                if len == 0 {
+                    self.pending.push_back(END_OF_TRANSMISSION);
+                } else {
+                    let value = self.require_byte()?;
+
+                    for _ in 0..len {
+                        self.pending.push_back(value);
+                    }
+                }
+            } else {
+                self.pending.push_back(byte);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<R: std::io::Read> std::io::Read for RleReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.fill()?;
+
+        let n = buf.len().min(self.pending.len());
+
+        for slot in &mut buf[..n] {
+            *slot = self.pending.pop_front().expect("checked by min above");
+        }
+
+        Ok(n)
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -108,7 +684,7 @@ mod tests {
 
     #[test]
     fn no_change_compress() {
-        let exp1 = &[0, 1, 2, 3, 4, 5, 6, 7];
+        let exp1 = &[0, 1, 2, 3, 5, 6, 7];
         let exp2 = &[0, 0, 0, 0, 0];
         let exp3 = &[0, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0];
 
@@ -118,26 +694,18 @@ mod tests {
     }
 
     #[test]
-    fn simple_compress() {
-        let six = 6u32.to_be_bytes();
-        let sixty_four = 64u32.to_be_bytes();
+    fn escapes_literal_eot() {
+        let data = &[1, END_OF_TRANSMISSION, 2, 3];
+
+        assert_eq!(compress(data), &[1, END_OF_TRANSMISSION, 0, 2, 3]);
+        assert_eq!(decompress(compress(data)).unwrap(), data);
+    }
 
+    #[test]
+    fn simple_compress() {
         assert_eq!(
             compress(&[0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1]),
-            &[
-                END_OF_TRANSMISSION,
-                six[0],
-                six[1],
-                six[2],
-                six[3],
-                0,
-                END_OF_TRANSMISSION,
-                six[0],
-                six[1],
-                six[2],
-                six[3],
-                1
-            ]
+            &[END_OF_TRANSMISSION, 6, 0, END_OF_TRANSMISSION, 6, 1]
         );
 
         assert_eq!(
@@ -146,17 +714,166 @@ mod tests {
                 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
                 0, 0, 0, 0, 0, 0, 0, 0, 64, 64, 230
             ]),
-            &[
-                END_OF_TRANSMISSION,
-                sixty_four[0],
-                sixty_four[1],
-                sixty_four[2],
-                sixty_four[3],
-                0,
-                64,
-                64,
-                230
-            ]
+            &[END_OF_TRANSMISSION, 64, 0, 64, 64, 230]
         );
     }
+
+    #[test]
+    fn large_run_uses_multi_byte_varint() {
+        let data = vec![9u8; 300];
+        let compressed = compress(&data);
+
+        assert_eq!(compressed, &[END_OF_TRANSMISSION, 0xFD, 44, 1, 9]);
+        assert_eq!(decompress(&compressed).unwrap(), data);
+    }
+
+    #[test]
+    fn round_trip() {
+        let exp1 = &[0, 1, 2, 3, 4, 5, 6, 7];
+        let exp2 = &[0, 0, 0, 0, 0];
+        let exp3 = &[0, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0];
+        let exp4 = &[44, 43, 6, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 3];
+        let exp5 = &[1, END_OF_TRANSMISSION, END_OF_TRANSMISSION, 2, 3];
+
+        for exp in [&exp1[..], &exp2[..], &exp3[..], &exp4[..], &exp5[..]] {
+            assert_eq!(decompress(compress(exp)).unwrap(), exp);
+        }
+    }
+
+    #[test]
+    fn truncated_block_errors() {
+        // missing varint entirely
+        assert_eq!(
+            decompress(&[END_OF_TRANSMISSION]),
+            Err(DecompressError::UnexpectedEof)
+        );
+        // a valid run length with no following data byte
+        assert_eq!(
+            decompress(&[END_OF_TRANSMISSION, 6]),
+            Err(DecompressError::UnexpectedEof)
+        );
+        // a multi-byte varint prefix with not enough bytes left to read
+        assert_eq!(
+            decompress(&[END_OF_TRANSMISSION, 0xFD, 0]),
+            Err(DecompressError::UnexpectedEof)
+        );
+    }
+
+    #[test]
+    fn packbits_round_trip() {
+        let exp1 = &[0, 1, 2, 3, 4, 5, 6, 7];
+        let exp2 = &[0, 0, 0, 0, 0];
+        let exp3 = &[0, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0];
+        let exp4 = &[44, 43, 6, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 3];
+        let exp5 = &[1, 2, 2, 2, 3];
+
+        for exp in [&exp1[..], &exp2[..], &exp3[..], &exp4[..], &exp5[..]] {
+            assert_eq!(decompress_packbits(compress_packbits(exp)).unwrap(), exp);
+        }
+    }
+
+    #[test]
+    fn packbits_simple_compress() {
+        assert_eq!(compress_packbits(&[1, 2, 2, 2, 3]), &[0, 1, 254, 2, 0, 3]);
+    }
+
+    #[test]
+    fn packbits_long_run_splits_at_128() {
+        let data = vec![7u8; 200];
+        let compressed = compress_packbits(&data);
+
+        assert_eq!(compressed, &[(1 - 128i32) as i8 as u8, 7, (1 - 72i32) as i8 as u8, 7]);
+        assert_eq!(decompress_packbits(&compressed).unwrap(), data);
+    }
+
+    #[test]
+    fn packbits_truncated_block_errors() {
+        assert_eq!(
+            decompress_packbits(&[2, 1]),
+            Err(PackBitsError::UnexpectedEof)
+        );
+        assert_eq!(
+            decompress_packbits(&[254u8]),
+            Err(PackBitsError::UnexpectedEof)
+        );
+    }
+
+    #[test]
+    fn writer_coalesces_run_across_writes() {
+        use std::io::Write;
+
+        let mut writer = RleWriter::new(Vec::new());
+        writer.write_all(&[1, 1, 1]).unwrap();
+        writer.write_all(&[1, 1, 1, 2]).unwrap();
+        let compressed = writer.finish().unwrap();
+
+        assert_eq!(compressed, compress(&[1, 1, 1, 1, 1, 1, 2]));
+    }
+
+    #[test]
+    fn writer_reader_round_trip() {
+        use std::io::{Read, Write};
+
+        let exp = &[44, 43, 6, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 3];
+
+        let mut writer = RleWriter::new(Vec::new());
+        writer.write_all(exp).unwrap();
+        let compressed = writer.finish().unwrap();
+
+        let mut reader = RleReader::new(compressed.as_slice());
+        let mut decompressed = Vec::new();
+        reader.read_to_end(&mut decompressed).unwrap();
+
+        assert_eq!(decompressed, exp);
+    }
+
+    #[test]
+    fn writer_drop_flushes_pending_run() {
+        use std::io::Write;
+
+        let mut output = Vec::new();
+        {
+            let mut writer = RleWriter::new(&mut output);
+            writer.write_all(&[9, 9, 9, 9, 9, 9]).unwrap();
+        }
+
+        assert_eq!(decompress(&output).unwrap(), &[9, 9, 9, 9, 9, 9]);
+    }
+
+    #[test]
+    fn compress_default_matches_balanced_level() {
+        let data = &[0, 0, 0, 0, 0, 0, 1];
+
+        assert_eq!(
+            compress(data),
+            compress_with(data, Config::from(CompressionLevel::Balanced))
+        );
+    }
+
+    #[test]
+    fn threshold_pins_output_for_same_input() {
+        let data = &[1, 1, 1, 1, 1, 2];
+
+        assert_eq!(
+            compress_with(data, Config::from(CompressionLevel::Aggressive)),
+            &[END_OF_TRANSMISSION, 5, 1, 2]
+        );
+        assert_eq!(
+            compress_with(data, Config::new().threshold(16)),
+            &[1, 1, 1, 1, 1, 2]
+        );
+    }
+
+    #[test]
+    fn threshold_is_clamped_to_one() {
+        let data = &[5, 5, 9, 9, 9];
+
+        // a threshold of 0 must behave identically to a threshold of 1, since
+        // a real run can never represent a length of 0 bytes
+        let zero = compress_with(data, Config::new().threshold(0));
+        let one = compress_with(data, Config::new().threshold(1));
+
+        assert_eq!(zero, one);
+        assert_eq!(decompress(&zero).unwrap(), data);
+    }
 }